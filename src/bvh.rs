@@ -0,0 +1,196 @@
+use super::scene::*;
+use super::rendering::*;
+
+enum BvhNode {
+    Leaf {
+        bounds: Aabb,
+        index: usize,
+    },
+    Interior {
+        bounds: Aabb,
+        left: usize,
+        right: usize,
+    },
+}
+
+impl BvhNode {
+    fn bounds(&self) -> Aabb {
+        match *self {
+            BvhNode::Leaf { bounds, .. } => bounds,
+            BvhNode::Interior { bounds, .. } => bounds,
+        }
+    }
+}
+
+/// A binary bounding-volume hierarchy over a scene's elements, used by `Scene::trace` to avoid
+/// a linear scan of every primitive for every ray. Nodes are stored flat in `nodes`, with `root`
+/// indexing the top of the tree (`None` for an empty scene).
+pub struct Bvh {
+    nodes: Vec<BvhNode>,
+    root: Option<usize>,
+}
+
+impl Bvh {
+    pub fn build(elements: &[Element]) -> Bvh {
+        let mut nodes = Vec::new();
+        if elements.is_empty() {
+            return Bvh { nodes, root: None };
+        }
+
+        let indices: Vec<usize> = (0..elements.len()).collect();
+        let root = Bvh::build_node(elements, indices, &mut nodes);
+        Bvh { nodes, root: Some(root) }
+    }
+
+    // Recursively splits `indices` along the axis of largest centroid spread at the median,
+    // pushing nodes into the shared flat `nodes` vec and returning the index of the node built.
+    fn build_node(elements: &[Element], mut indices: Vec<usize>, nodes: &mut Vec<BvhNode>) -> usize {
+        if indices.len() == 1 {
+            let index = indices[0];
+            nodes.push(BvhNode::Leaf { bounds: elements[index].bounding_box(), index });
+            return nodes.len() - 1;
+        }
+
+        let centroids: Vec<Point> = indices.iter().map(|&i| elements[i].bounding_box().centroid()).collect();
+        let mut centroid_min = centroids[0];
+        let mut centroid_max = centroids[0];
+        for c in &centroids {
+            centroid_min.x = centroid_min.x.min(c.x);
+            centroid_min.y = centroid_min.y.min(c.y);
+            centroid_min.z = centroid_min.z.min(c.z);
+            centroid_max.x = centroid_max.x.max(c.x);
+            centroid_max.y = centroid_max.y.max(c.y);
+            centroid_max.z = centroid_max.z.max(c.z);
+        }
+        let spread = centroid_max - centroid_min;
+
+        if spread.x >= spread.y && spread.x >= spread.z {
+            indices.sort_by(|&a, &b| elements[a].bounding_box().centroid().x.partial_cmp(&elements[b].bounding_box().centroid().x).unwrap());
+        } else if spread.y >= spread.z {
+            indices.sort_by(|&a, &b| elements[a].bounding_box().centroid().y.partial_cmp(&elements[b].bounding_box().centroid().y).unwrap());
+        } else {
+            indices.sort_by(|&a, &b| elements[a].bounding_box().centroid().z.partial_cmp(&elements[b].bounding_box().centroid().z).unwrap());
+        }
+
+        let right_indices = indices.split_off(indices.len() / 2);
+        let left_indices = indices;
+
+        let left = Bvh::build_node(elements, left_indices, nodes);
+        let right = Bvh::build_node(elements, right_indices, nodes);
+        let bounds = nodes[left].bounds().union(&nodes[right].bounds());
+        nodes.push(BvhNode::Interior { bounds, left, right });
+        nodes.len() - 1
+    }
+
+    pub fn trace<'a>(&self, elements: &'a [Element], ray: &Ray) -> Option<Intersection<'a>> {
+        match self.root {
+            Some(root) => self.trace_node(root, elements, ray, None),
+            None => None,
+        }
+    }
+
+    fn trace_node<'a>(&self, node_index: usize, elements: &'a [Element], ray: &Ray, closest: Option<Intersection<'a>>) -> Option<Intersection<'a>> {
+        let node = &self.nodes[node_index];
+        let entry = match node.bounds().intersect(ray) {
+            Some(t) => t,
+            None => return closest,
+        };
+        if let Some(ref hit) = closest {
+            if entry > hit.distance {
+                return closest;
+            }
+        }
+
+        match *node {
+            BvhNode::Leaf { index, .. } => {
+                match elements[index].intersect(ray) {
+                    Some(distance) => {
+                        let is_closer = match closest {
+                            Some(ref hit) => distance < hit.distance,
+                            None => true,
+                        };
+                        if is_closer {
+                            Some(Intersection::new(distance, &elements[index]))
+                        } else {
+                            closest
+                        }
+                    }
+                    None => closest,
+                }
+            }
+            BvhNode::Interior { left, right, .. } => {
+                let left_entry = self.nodes[left].bounds().intersect(ray);
+                let right_entry = self.nodes[right].bounds().intersect(ray);
+                let (first, second) = match (left_entry, right_entry) {
+                    (Some(l), Some(r)) if r < l => (right, left),
+                    _ => (left, right),
+                };
+                let closest = self.trace_node(first, elements, ray, closest);
+                self.trace_node(second, elements, ray, closest)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+fn linear_scan(elements: &[Element], ray: &Ray) -> Option<f64> {
+    elements
+        .iter()
+        .filter_map(|e| e.intersect(ray))
+        .fold(None, |closest, distance| match closest {
+            Some(c) if c < distance => Some(c),
+            _ => Some(distance),
+        })
+}
+
+#[cfg(test)]
+fn test_sphere_at(x: f64) -> Element {
+    Element::Sphere(Sphere {
+        center: Point { x, y: 0.0, z: -5.0 },
+        radius: 0.5,
+        material: Material {
+            skin: Coloration::Color(Color { red: 1.0, green: 1.0, blue: 1.0 }),
+            albedo: 1.0,
+            reflectivity: 0.0,
+            refractive_index: None,
+            transparency: None,
+            emissive: None,
+        },
+    })
+}
+
+#[test]
+fn test_bvh_matches_linear_scan_on_a_hit() {
+    let elements = vec![test_sphere_at(-3.0), test_sphere_at(0.0), test_sphere_at(3.0)];
+    let bvh = Bvh::build(&elements);
+    let ray = Ray {
+        origin: Point { x: 0.0, y: 0.0, z: 0.0 },
+        direction: Vector3 { x: 0.0, y: 0.0, z: -1.0 },
+    };
+    let expected = linear_scan(&elements, &ray);
+    let actual = bvh.trace(&elements, &ray).map(|i| i.distance);
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn test_bvh_matches_linear_scan_on_a_miss() {
+    let elements = vec![test_sphere_at(-3.0), test_sphere_at(0.0), test_sphere_at(3.0)];
+    let bvh = Bvh::build(&elements);
+    let ray = Ray {
+        origin: Point { x: 0.0, y: 100.0, z: 0.0 },
+        direction: Vector3 { x: 0.0, y: 0.0, z: -1.0 },
+    };
+    assert_eq!(linear_scan(&elements, &ray), None);
+    assert!(bvh.trace(&elements, &ray).is_none());
+}
+
+#[test]
+fn test_bvh_empty_scene_never_hits() {
+    let elements: Vec<Element> = vec![];
+    let bvh = Bvh::build(&elements);
+    let ray = Ray {
+        origin: Point { x: 0.0, y: 0.0, z: 0.0 },
+        direction: Vector3 { x: 0.0, y: 0.0, z: -1.0 },
+    };
+    assert!(bvh.trace(&elements, &ray).is_none());
+}
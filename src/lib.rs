@@ -1,36 +1,194 @@
 mod scene;
 mod rendering;
+mod bvh;
+mod obj;
+mod path_tracing;
+mod scene_loader;
 
 use image::*;
 use scene::*;
 use rendering::*;
+#[cfg(test)]
+use bvh::*;
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+pub use obj::load_obj;
+pub use path_tracing::{render_path_traced, render_path_traced_with_progress};
+
+const BACKGROUND_COLOR: Color = Color {
+    red: 50.0 / 255.0,
+    green: 100.0 / 255.0,
+    blue: 220.0 / 255.0,
+};
 
 pub fn render(scene: &Scene) -> DynamicImage {
+    render_with_progress(scene, |_percent| {})
+}
+
+/// Renders `scene`, tracing scanlines in parallel across the available cores. `progress` is
+/// called from worker threads after each completed scanline with the fraction of rows done so
+/// far (`0.0..=1.0`); pass a no-op closure to ignore it.
+pub fn render_with_progress<F: Fn(f32) + Sync>(scene: &Scene, progress: F) -> DynamicImage {
+    let completed_rows = AtomicUsize::new(0);
+
+    let rows: Vec<Vec<Rgba<u8>>> = (0..scene.height)
+        .into_par_iter()
+        .map(|y| {
+            let row = (0..scene.width)
+                .map(|x| {
+                    let ray = Ray::create_prime(x, y, scene);
+                    let color = cast_ray(scene, &ray, 0);
+                    let (r, g, b) = tone_map(color, scene);
+                    Rgba::from_channels(r, g, b, 0)
+                })
+                .collect();
+
+            let done = completed_rows.fetch_add(1, Ordering::SeqCst) + 1;
+            progress(done as f32 / scene.height as f32);
+            row
+        })
+        .collect();
+
     let mut image = DynamicImage::new_rgb8(scene.width, scene.height);
-    let background = Rgba::from_channels(50, 100, 220, 0);
-    for x in 0..scene.width {
-        for y in 0..scene.height {
-            let ray = Ray::create_prime(x, y, scene);
-
-            match scene.trace(&ray) {
-                Some(intersection) => {
-                    let color: Color = get_color(scene, &ray, &intersection);
-                    let r = (color.red * 255.0) as u8;
-                    let b = (color.blue * 255.0) as u8;
-                    let g = (color.green * 255.0) as u8;
-                    image.put_pixel(x, y, Rgba::from_channels(r, g, b, 0));
-                }
-                None => image.put_pixel(x, y, background)
-            };
+    for (y, row) in rows.into_iter().enumerate() {
+        for (x, pixel) in row.into_iter().enumerate() {
+            image.put_pixel(x as u32, y as u32, pixel);
         }
     }
     image
 }
 
-fn get_color(scene: &Scene, ray: &Ray, intersection: &Intersection) -> Color {
+// Maps accumulated HDR radiance down to a displayable 8-bit-per-channel color: an exposure
+// scale, then the Reinhard operator `c/(1+c)` to compress highlights into `[0,1]` instead of
+// clipping them, then gamma correction before quantizing. `scene.exposure`/`scene.gamma` let
+// callers balance bright or multi-light scenes without blowout.
+fn tone_map(color: Color, scene: &Scene) -> (u8, u8, u8) {
+    let exposed = color * scene.exposure;
+    let reinhard = Color {
+        red: exposed.red / (1.0 + exposed.red),
+        green: exposed.green / (1.0 + exposed.green),
+        blue: exposed.blue / (1.0 + exposed.blue),
+    };
+    let gamma_exponent = 1.0 / scene.gamma;
+    let r = (reinhard.red.max(0.0).powf(gamma_exponent) * 255.0).round() as u8;
+    let g = (reinhard.green.max(0.0).powf(gamma_exponent) * 255.0).round() as u8;
+    let b = (reinhard.blue.max(0.0).powf(gamma_exponent) * 255.0).round() as u8;
+    (r, g, b)
+}
+
+#[cfg(test)]
+fn test_scene_with_exposure_gamma(exposure: f32, gamma: f32) -> Scene {
+    Scene {
+        width: 1,
+        height: 1,
+        camera: Camera {
+            position: Point { x: 0.0, y: 0.0, z: 0.0 },
+            look_at: Point { x: 0.0, y: 0.0, z: -1.0 },
+            up: Vector3 { x: 0.0, y: 1.0, z: 0.0 },
+            fov: 90.0,
+        },
+        elements: vec![],
+        lights: vec![],
+        shadow_bias: 1e-10,
+        max_depth: 5,
+        samples_per_pixel: 1,
+        exposure,
+        gamma,
+        bvh: Bvh::build(&[]),
+    }
+}
+
+#[test]
+fn test_tone_map_black_is_black() {
+    let scene = test_scene_with_exposure_gamma(1.0, 2.2);
+    let black = Color { red: 0.0, green: 0.0, blue: 0.0 };
+    assert_eq!(tone_map(black, &scene), (0, 0, 0));
+}
+
+#[test]
+fn test_tone_map_reinhard_matches_formula() {
+    let scene = test_scene_with_exposure_gamma(1.0, 1.0);
+    // c/(1+c) for c=1.0 is 0.5, which quantizes to 128 -- pin the exact value so a change to
+    // the operator (e.g. swapping in a plain clip) shows up as a test failure.
+    let color = Color { red: 1.0, green: 1.0, blue: 1.0 };
+    assert_eq!(tone_map(color, &scene), (128, 128, 128));
+}
+
+#[test]
+fn test_tone_map_compresses_overbright_radiance() {
+    let scene = test_scene_with_exposure_gamma(1.0, 1.0);
+    // Reinhard asymptotically approaches full brightness but a naive clip would have hit 255
+    // much sooner; two wildly different HDR values should still land close together.
+    let bright = tone_map(Color { red: 100.0, green: 100.0, blue: 100.0 }, &scene);
+    let brighter = tone_map(Color { red: 10_000.0, green: 10_000.0, blue: 10_000.0 }, &scene);
+    assert!((bright.0 as i16 - brighter.0 as i16).abs() <= 5);
+}
+
+#[test]
+fn test_tone_map_exposure_brightens_midtones() {
+    let dim_scene = test_scene_with_exposure_gamma(1.0, 1.0);
+    let bright_scene = test_scene_with_exposure_gamma(4.0, 1.0);
+    let color = Color { red: 0.2, green: 0.2, blue: 0.2 };
+    let (dim_r, _, _) = tone_map(color, &dim_scene);
+    let (bright_r, _, _) = tone_map(color, &bright_scene);
+    assert!(bright_r > dim_r);
+}
+
+// Traces a ray and shades whatever it hits, recursing into reflection/refraction rays up to
+// `scene.max_depth`. Misses return the scene background.
+fn cast_ray(scene: &Scene, ray: &Ray, depth: u32) -> Color {
+    match scene.trace(ray) {
+        Some(intersection) => get_color(scene, ray, &intersection, depth),
+        None => BACKGROUND_COLOR,
+    }
+}
+
+// Schlick's approximation of the Fresnel equations: the fraction of light reflected (as
+// opposed to transmitted) at a dielectric boundary, given the incident ray and `index`, the
+// refractive index of the material on the far side of the surface.
+fn fresnel(incident: Vector3, normal: Vector3, index: f32) -> f64 {
+    let mut cos_i = incident.dot(&normal);
+    let mut eta_i = 1.0;
+    let mut eta_t = index as f64;
+    if cos_i > 0.0 {
+        ::std::mem::swap(&mut eta_i, &mut eta_t);
+    } else {
+        cos_i = -cos_i;
+    }
+
+    let r0 = ((eta_i - eta_t) / (eta_i + eta_t)).powi(2);
+    r0 + (1.0 - r0) * (1.0 - cos_i).powi(5)
+}
+
+fn get_color(scene: &Scene, ray: &Ray, intersection: &Intersection, depth: u32) -> Color {
     let hit_point = ray.origin + (ray.direction * intersection.distance);
     let surface_normal = intersection.element.surface_normal(&hit_point);
+    let material = intersection.element.material();
+
+    let mut color = shade_diffuse(scene, intersection, hit_point, surface_normal);
+
+    if depth < scene.max_depth && (material.reflectivity > 0.0 || material.transparency.is_some()) {
+        let index = material.refractive_index.unwrap_or(1.0);
+        let reflection_ray = Ray::create_reflection(surface_normal, ray.direction, hit_point, scene.shadow_bias);
+        let reflected_color = cast_ray(scene, &reflection_ray, depth + 1);
+
+        if let Some(transparency) = material.transparency {
+            let kr = fresnel(ray.direction, surface_normal, index) as f32;
+            let refracted_color = match Ray::create_transmission(surface_normal, ray.direction, hit_point, scene.shadow_bias, index) {
+                Some(transmission_ray) => cast_ray(scene, &transmission_ray, depth + 1),
+                None => reflected_color, // total internal reflection
+            };
+            let refraction_blend = reflected_color * kr + refracted_color * (1.0 - kr);
+            color = color * (1.0 - transparency) + refraction_blend * transparency;
+        } else {
+            color = color * (1.0 - material.reflectivity) + reflected_color * material.reflectivity;
+        }
+    }
 
+    color
+}
+
+fn shade_diffuse(scene: &Scene, intersection: &Intersection, hit_point: Point, surface_normal: Vector3) -> Color {
     let mut color = Color {
         red: 0.0,
         blue: 0.0,
@@ -74,11 +232,7 @@ fn get_color(scene: &Scene, ray: &Ray, intersection: &Intersection) -> Color {
 fn test_can_render_scene() {
     let checkers = image::open("checkers.png").unwrap();
 
-    let scene = Scene {
-        width: 800,
-        height: 600,
-        fov: 90.0,
-        elements: vec! [
+    let elements = vec! [
              Element::Sphere( Sphere {
                 center: Point {
                     x: 0.0,
@@ -93,6 +247,10 @@ fn test_can_render_scene() {
                         blue: 0.4,
                     }),
                     albedo: 0.5,
+                    reflectivity: 0.3,
+                    refractive_index: None,
+                    transparency: None,
+                    emissive: None,
                 },
             }),
             Element::Sphere( Sphere {
@@ -109,6 +267,10 @@ fn test_can_render_scene() {
                         blue: 0.4,
                     }),
                     albedo: 1.5,
+                    reflectivity: 0.0,
+                    refractive_index: None,
+                    transparency: None,
+                    emissive: None,
                 }
             }),
             Element::Sphere( Sphere {
@@ -120,7 +282,11 @@ fn test_can_render_scene() {
                 radius: 1.7,
                 material: Material {
                     skin : Coloration::Texture(checkers.clone()),
-                    albedo: 2.0,   
+                    albedo: 2.0,
+                    reflectivity: 0.0,
+                    refractive_index: None,
+                    transparency: None,
+                    emissive: None,
                 }
             }),
             Element::Plane( Plane {
@@ -137,9 +303,37 @@ fn test_can_render_scene() {
                 material: Material {
                     skin : Coloration::Texture(checkers.clone()),
                     albedo: 2.0,
+                    reflectivity: 0.0,
+                    refractive_index: None,
+                    transparency: None,
+                    emissive: None,
                 }
             })
-        ],
+        ];
+    let bvh = Bvh::build(&elements);
+
+    let scene = Scene {
+        width: 800,
+        height: 600,
+        camera: Camera {
+            position: Point {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            look_at: Point {
+                x: 0.0,
+                y: 0.0,
+                z: -1.0,
+            },
+            up: Vector3 {
+                x: 0.0,
+                y: 1.0,
+                z: 0.0,
+            },
+            fov: 90.0,
+        },
+        elements,
         lights: vec![
             Light::Directional( DirectionalLight {
                 direction: Vector3 {
@@ -181,7 +375,12 @@ fn test_can_render_scene() {
                 intensity: 3.0,
             })
         ],
-        shadow_bias: 1e-10
+        shadow_bias: 1e-10,
+        max_depth: 5,
+        samples_per_pixel: 32,
+        exposure: 1.0,
+        gamma: 2.2,
+        bvh,
     };
 
     let img: DynamicImage = render(&scene);
@@ -0,0 +1,82 @@
+use std::fs;
+use super::scene::*;
+
+/// Loads a Wavefront `.obj` mesh from `path`, triangulating each face and applying a clone of
+/// `material` to every triangle. Recognizes `v`, `vn`, `vt`, and `f` records; anything else
+/// (comments, groups, smoothing flags, ...) is ignored.
+pub fn load_obj(path: &str, material: Material) -> Vec<Element> {
+    let contents = fs::read_to_string(path).unwrap();
+
+    let mut positions: Vec<Point> = Vec::new();
+    let mut normals: Vec<Vector3> = Vec::new();
+    let mut elements: Vec<Element> = Vec::new();
+
+    for line in contents.lines() {
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("v") => positions.push(parse_vector(tokens)),
+            Some("vn") => normals.push(parse_vector(tokens)),
+            Some("vt") => {} // texture coordinates aren't attached to triangles yet
+            Some("f") => {
+                let face: Vec<(usize, Option<usize>)> = tokens.map(parse_face_vertex).collect();
+                for i in 1..face.len() - 1 {
+                    let (ia, na) = face[0];
+                    let (ib, nb) = face[i];
+                    let (ic, nc) = face[i + 1];
+                    let vertex_normals = match (na, nb, nc) {
+                        (Some(na), Some(nb), Some(nc)) => Some((normals[na], normals[nb], normals[nc])),
+                        _ => None,
+                    };
+                    elements.push(Element::Triangle(Triangle {
+                        a: positions[ia],
+                        b: positions[ib],
+                        c: positions[ic],
+                        normals: vertex_normals,
+                        material: material.clone(),
+                    }));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    elements
+}
+
+fn parse_vector<'a, I: Iterator<Item = &'a str>>(tokens: I) -> Vector3 {
+    let coords: Vec<f64> = tokens.map(|t| t.parse().unwrap()).collect();
+    Vector3 { x: coords[0], y: coords[1], z: coords[2] }
+}
+
+// Parses a face record's per-vertex token (`v`, `v/vt`, `v//vn`, or `v/vt/vn`) into a 0-based
+// position index and an optional 0-based normal index.
+fn parse_face_vertex(token: &str) -> (usize, Option<usize>) {
+    let parts: Vec<&str> = token.split('/').collect();
+    let position = parts[0].parse::<usize>().unwrap() - 1;
+    let normal = if parts.len() == 3 && !parts[2].is_empty() {
+        Some(parts[2].parse::<usize>().unwrap() - 1)
+    } else {
+        None
+    };
+    (position, normal)
+}
+
+#[test]
+fn test_parse_face_vertex_position_only() {
+    assert_eq!(parse_face_vertex("3"), (2, None));
+}
+
+#[test]
+fn test_parse_face_vertex_position_and_texture() {
+    assert_eq!(parse_face_vertex("3/4"), (2, None));
+}
+
+#[test]
+fn test_parse_face_vertex_position_and_normal() {
+    assert_eq!(parse_face_vertex("3//5"), (2, Some(4)));
+}
+
+#[test]
+fn test_parse_face_vertex_position_texture_and_normal() {
+    assert_eq!(parse_face_vertex("3/4/5"), (2, Some(4)));
+}
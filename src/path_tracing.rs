@@ -0,0 +1,116 @@
+use super::scene::*;
+use super::rendering::*;
+use super::{BACKGROUND_COLOR, tone_map};
+use image::*;
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::f64::consts::PI;
+
+const RUSSIAN_ROULETTE_START_BOUNCE: u32 = 3;
+
+/// Path-traces `scene`, averaging `scene.samples_per_pixel` random-walk samples per pixel to
+/// estimate global illumination. Unlike `render`, this captures soft shadows and color bleeding
+/// at the cost of per-pixel noise that only cleans up with enough samples.
+pub fn render_path_traced(scene: &Scene) -> DynamicImage {
+    render_path_traced_with_progress(scene, |_percent| {})
+}
+
+pub fn render_path_traced_with_progress<F: Fn(f32) + Sync>(scene: &Scene, progress: F) -> DynamicImage {
+    let completed_rows = AtomicUsize::new(0);
+
+    let rows: Vec<Vec<Rgba<u8>>> = (0..scene.height)
+        .into_par_iter()
+        .map(|y| {
+            let row = (0..scene.width)
+                .map(|x| {
+                    let mut accumulated = Color { red: 0.0, green: 0.0, blue: 0.0 };
+                    for _ in 0..scene.samples_per_pixel {
+                        let ray = Ray::create_prime(x, y, scene);
+                        accumulated = accumulated + trace_path(scene, &ray, 0);
+                    }
+                    let color = accumulated * (1.0 / scene.samples_per_pixel as f32);
+                    let (r, g, b) = tone_map(color, scene);
+                    Rgba::from_channels(r, g, b, 0)
+                })
+                .collect();
+
+            let done = completed_rows.fetch_add(1, Ordering::SeqCst) + 1;
+            progress(done as f32 / scene.height as f32);
+            row
+        })
+        .collect();
+
+    let mut image = DynamicImage::new_rgb8(scene.width, scene.height);
+    for (y, row) in rows.into_iter().enumerate() {
+        for (x, pixel) in row.into_iter().enumerate() {
+            image.put_pixel(x as u32, y as u32, pixel);
+        }
+    }
+    image
+}
+
+fn trace_path(scene: &Scene, ray: &Ray, depth: u32) -> Color {
+    if depth >= scene.max_depth {
+        return Color { red: 0.0, green: 0.0, blue: 0.0 };
+    }
+
+    let intersection = match scene.trace(ray) {
+        Some(intersection) => intersection,
+        None => return BACKGROUND_COLOR,
+    };
+
+    let hit_point = ray.origin + (ray.direction * intersection.distance);
+    let normal = intersection.element.surface_normal(&hit_point);
+    let material = intersection.element.material();
+    let emitted = material.emissive.unwrap_or(Color { red: 0.0, green: 0.0, blue: 0.0 });
+
+    // With cosine-weighted hemisphere sampling, the Lambertian BRDF (albedo/pi) and the sample
+    // pdf (cos theta/pi) cancel, leaving the reflectance itself as the estimator's multiplier.
+    // Clamp to [0, 1] since the repo's albedo isn't guaranteed to be an energy-conserving
+    // reflectance (the test scene uses 1.5 and 2.0) and anything above 1 would gain energy.
+    let tc = intersection.element.texture_coordinates(&hit_point);
+    let diffuse_color = intersection.element.skin().color(&tc) * material.albedo.min(1.0);
+
+    let mut roulette_weight = 1.0;
+    if depth >= RUSSIAN_ROULETTE_START_BOUNCE {
+        let continue_probability = diffuse_color.red.max(diffuse_color.green).max(diffuse_color.blue).min(0.95);
+        if continue_probability <= 0.0 || rand::random::<f32>() > continue_probability {
+            return emitted;
+        }
+        roulette_weight = 1.0 / continue_probability;
+    }
+
+    let bounce_direction = cosine_weighted_hemisphere(normal);
+    let bounce_ray = Ray {
+        origin: hit_point + (normal * scene.shadow_bias),
+        direction: bounce_direction,
+    };
+    let indirect = trace_path(scene, &bounce_ray, depth + 1);
+
+    emitted + diffuse_color * indirect * roulette_weight
+}
+
+// Cosine-weighted sample of the hemisphere around `normal`, rotated from the canonical
+// z-up local frame into world space via a tangent frame built from `normal`.
+fn cosine_weighted_hemisphere(normal: Vector3) -> Vector3 {
+    let r1: f64 = rand::random();
+    let r2: f64 = rand::random();
+    let phi = 2.0 * PI * r1;
+    let sin_theta = r2.sqrt();
+
+    let local = Vector3 {
+        x: phi.cos() * sin_theta,
+        y: phi.sin() * sin_theta,
+        z: (1.0 - r2).sqrt(),
+    };
+
+    let up = if normal.x.abs() > 0.99 {
+        Vector3 { x: 0.0, y: 1.0, z: 0.0 }
+    } else {
+        Vector3 { x: 1.0, y: 0.0, z: 0.0 }
+    };
+    let tangent = up.cross(&normal).normalize();
+    let bitangent = normal.cross(&tangent);
+
+    (tangent * local.x + bitangent * local.y + normal * local.z).normalize()
+}
@@ -9,19 +9,19 @@ pub struct Ray {
 impl Ray {
     pub fn create_prime(x: u32, y: u32, scene: &Scene) -> Ray {
         assert!(scene.width > scene.height);
-        let fov_adjustment = (scene.fov.to_radians() / 2.0).tan();
+        let camera = &scene.camera;
+        let fov_adjustment = (camera.fov.to_radians() / 2.0).tan();
         let aspect_ratio = (scene.width as f64) / (scene.height as f64);
         let sensor_x = ((((x as f64 + 0.5) / scene.width as f64) * 2.0 - 1.0) * aspect_ratio) * fov_adjustment;
         let sensor_y = (1.0 - ((y as f64 + 0.5) / scene.height as f64) * 2.0) * fov_adjustment;
-        
+
+        let forward = (camera.look_at - camera.position).normalize();
+        let right = forward.cross(&camera.up).normalize();
+        let true_up = right.cross(&forward);
+
         Ray {
-            origin: Point{x:0.0,y:0.0,z:0.0},
-            direction: Vector3 {
-                    x: sensor_x,
-                    y: sensor_y,
-                    z: -1.0,
-                }
-                .normalize(),
+            origin: camera.position,
+            direction: (right * sensor_x + true_up * sensor_y + forward).normalize(),
         }
     }
 
@@ -31,6 +31,34 @@ impl Ray {
             direction: dir - (surface_normal * 2.0 * dir.dot(&surface_normal)),
         }
     }
+
+    /// Refracts `dir` through a surface with the given `surface_normal` using Snell's law.
+    /// Returns `None` on total internal reflection (the radicand under the square root goes
+    /// negative), in which case the caller should fall back to the reflected ray.
+    pub fn create_transmission(surface_normal: Vector3, dir: Vector3, pt: Point, shadow_bias: f64, index: f32) -> Option<Ray> {
+        let mut ref_n = surface_normal;
+        let mut eta_i = 1.0;
+        let mut eta_t = index as f64;
+        let mut cos_i = dir.dot(&surface_normal);
+        if cos_i < 0.0 {
+            cos_i = -cos_i;
+        } else {
+            ref_n = surface_normal * -1.0;
+            ::std::mem::swap(&mut eta_i, &mut eta_t);
+        }
+
+        let eta = eta_i / eta_t;
+        let k = 1.0 - eta * eta * (1.0 - cos_i * cos_i);
+        if k < 0.0 {
+            None
+        } else {
+            let direction = (dir * eta) + (ref_n * (eta * cos_i - k.sqrt()));
+            Some(Ray {
+                origin: pt - (ref_n * shadow_bias),
+                direction,
+            })
+        }
+    }
 }
 
 
@@ -38,6 +66,49 @@ pub trait Intersectable {
     fn intersect(&self, ray: &Ray) -> Option<f64>;
     fn surface_normal(&self, p: &Point) -> Vector3;
     fn texture_coordinates(&self, hit_point: &Point) -> TextureCoordinates;
+    fn bounding_box(&self) -> Aabb;
+}
+
+impl Aabb {
+    /// Slab-based ray/AABB test. Returns the entry `t` (clamped to 0 when the ray
+    /// origin is already inside the box) so callers can prune subtrees that lie
+    /// farther away than the closest hit found so far.
+    pub fn intersect(&self, ray: &Ray) -> Option<f64> {
+        let mut t_min = f64::NEG_INFINITY;
+        let mut t_max = f64::INFINITY;
+
+        for axis in 0..3 {
+            let (origin, dir, min, max) = match axis {
+                0 => (ray.origin.x, ray.direction.x, self.min.x, self.max.x),
+                1 => (ray.origin.y, ray.direction.y, self.min.y, self.max.y),
+                _ => (ray.origin.z, ray.direction.z, self.min.z, self.max.z),
+            };
+
+            if dir.abs() < 1e-12 {
+                if origin < min || origin > max {
+                    return None;
+                }
+            } else {
+                let inv_dir = 1.0 / dir;
+                let mut t0 = (min - origin) * inv_dir;
+                let mut t1 = (max - origin) * inv_dir;
+                if t0 > t1 {
+                    ::std::mem::swap(&mut t0, &mut t1);
+                }
+                t_min = t_min.max(t0);
+                t_max = t_max.min(t1);
+                if t_min > t_max {
+                    return None;
+                }
+            }
+        }
+
+        if t_max < 0.0 {
+            None
+        } else {
+            Some(t_min.max(0.0))
+        }
+    }
 }
 
 impl <'a> Intersection<'a> {
@@ -51,10 +122,7 @@ impl <'a> Intersection<'a> {
 
 impl Scene {
     pub fn trace(&self, ray: &Ray) -> Option<Intersection> {
-        self.elements
-            .iter()
-            .filter_map(|s| s.intersect(ray).map(|d| Intersection::new(d, s)))
-            .min_by(|i1, i2| i1.distance.partial_cmp(&i2.distance).unwrap())
+        self.bvh.trace(&self.elements, ray)
     }
 }
 
@@ -63,12 +131,14 @@ impl Element {
         match *self {
             Element::Sphere(ref s) => &s.material.skin,
             Element::Plane(ref p) => &p.material.skin,
+            Element::Triangle(ref t) => &t.material.skin,
         }
     }
     pub fn material(&self) -> &Material {
         match *self {
             Element::Sphere(ref s) => &s.material,
             Element::Plane(ref p) => &p.material,
+            Element::Triangle(ref t) => &t.material,
         }
     }
 }
@@ -78,6 +148,7 @@ impl Intersectable for Element {
         match *self {
             Element::Sphere(ref s) => s.intersect(ray),
             Element::Plane(ref p) => p.intersect(ray),
+            Element::Triangle(ref t) => t.intersect(ray),
         }
     }
 
@@ -85,6 +156,7 @@ impl Intersectable for Element {
         match *self {
             Element::Sphere(ref e) => e.surface_normal(p),
             Element::Plane(ref e) => e.surface_normal(p),
+            Element::Triangle(ref e) => e.surface_normal(p),
         }
     }
 
@@ -92,6 +164,15 @@ impl Intersectable for Element {
         match *self {
             Element::Sphere(ref e) => e.texture_coordinates(hit_point),
             Element::Plane(ref e) => e.texture_coordinates(hit_point),
+            Element::Triangle(ref e) => e.texture_coordinates(hit_point),
+        }
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        match *self {
+            Element::Sphere(ref e) => e.bounding_box(),
+            Element::Plane(ref e) => e.bounding_box(),
+            Element::Triangle(ref e) => e.bounding_box(),
         }
     }
 }
@@ -114,6 +195,16 @@ impl Intersectable for Plane {
         self.normal * -1.0
     }
 
+    fn bounding_box(&self) -> Aabb {
+        // An infinite plane has no finite extent; use a very large box so it still
+        // gets a sane (if useless) bound for BVH purposes.
+        const HUGE: f64 = 1.0e10;
+        Aabb {
+            min: Point { x: -HUGE, y: -HUGE, z: -HUGE },
+            max: Point { x: HUGE, y: HUGE, z: HUGE },
+        }
+    }
+
     fn texture_coordinates(&self, hit_point: &Point) -> TextureCoordinates {
         let mut x_axis = self.normal.cross(&Vector3 {
             x: 0.0,
@@ -170,4 +261,181 @@ impl Intersectable for Sphere {
             y: (hit_vec.y / self.radius).acos() as f32 / f32::consts::PI,
         }
     }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb {
+            min: Point {
+                x: self.center.x - self.radius,
+                y: self.center.y - self.radius,
+                z: self.center.z - self.radius,
+            },
+            max: Point {
+                x: self.center.x + self.radius,
+                y: self.center.y + self.radius,
+                z: self.center.z + self.radius,
+            },
+        }
+    }
+}
+
+impl Triangle {
+    // Barycentric weights of `p` with respect to `b` and `c` (the weight of `a` is
+    // `1 - u - v`). Shared by `surface_normal` and `texture_coordinates` so both can be
+    // recovered from a hit point alone, the same way the other primitives work.
+    fn barycentric(&self, p: &Point) -> (f64, f64) {
+        let e1 = self.b - self.a;
+        let e2 = self.c - self.a;
+        let vp = *p - self.a;
+
+        let d00 = e1.dot(&e1);
+        let d01 = e1.dot(&e2);
+        let d11 = e2.dot(&e2);
+        let d20 = vp.dot(&e1);
+        let d21 = vp.dot(&e2);
+        let denom = d00 * d11 - d01 * d01;
+
+        let u = (d11 * d20 - d01 * d21) / denom;
+        let v = (d00 * d21 - d01 * d20) / denom;
+        (u, v)
+    }
+}
+
+impl Intersectable for Triangle {
+    // Moller-Trumbore ray/triangle intersection.
+    fn intersect(&self, ray: &Ray) -> Option<f64> {
+        let e1 = self.b - self.a;
+        let e2 = self.c - self.a;
+        let p = ray.direction.cross(&e2);
+        let det = e1.dot(&p);
+        if det.abs() < 1e-10 {
+            return None;
+        }
+
+        let inv_det = 1.0 / det;
+        let t_vec = ray.origin - self.a;
+        let u = t_vec.dot(&p) * inv_det;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+
+        let q = t_vec.cross(&e1);
+        let v = ray.direction.dot(&q) * inv_det;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+
+        let distance = e2.dot(&q) * inv_det;
+        if distance < 0.0 {
+            None
+        } else {
+            Some(distance)
+        }
+    }
+
+    fn surface_normal(&self, p: &Point) -> Vector3 {
+        match self.normals {
+            Some((na, nb, nc)) => {
+                let (u, v) = self.barycentric(p);
+                (na * (1.0 - u - v) + nb * u + nc * v).normalize()
+            }
+            None => {
+                let e1 = self.b - self.a;
+                let e2 = self.c - self.a;
+                e1.cross(&e2).normalize()
+            }
+        }
+    }
+
+    fn texture_coordinates(&self, hit_point: &Point) -> TextureCoordinates {
+        let (u, v) = self.barycentric(hit_point);
+        TextureCoordinates { x: u as f32, y: v as f32 }
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb {
+            min: Point {
+                x: self.a.x.min(self.b.x).min(self.c.x),
+                y: self.a.y.min(self.b.y).min(self.c.y),
+                z: self.a.z.min(self.b.z).min(self.c.z),
+            },
+            max: Point {
+                x: self.a.x.max(self.b.x).max(self.c.x),
+                y: self.a.y.max(self.b.y).max(self.c.y),
+                z: self.a.z.max(self.b.z).max(self.c.z),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+fn test_material() -> Material {
+    Material {
+        skin: Coloration::Color(Color { red: 1.0, green: 1.0, blue: 1.0 }),
+        albedo: 1.0,
+        reflectivity: 0.0,
+        refractive_index: None,
+        transparency: None,
+        emissive: None,
+    }
+}
+
+#[cfg(test)]
+fn test_triangle() -> Triangle {
+    Triangle {
+        a: Point { x: 0.0, y: 1.0, z: -2.0 },
+        b: Point { x: -1.0, y: -1.0, z: -2.0 },
+        c: Point { x: 1.0, y: -1.0, z: -2.0 },
+        normals: None,
+        material: test_material(),
+    }
+}
+
+#[test]
+fn test_triangle_intersect_hits_center() {
+    let triangle = test_triangle();
+    let ray = Ray {
+        origin: Point { x: 0.0, y: -0.3333, z: 0.0 },
+        direction: Vector3 { x: 0.0, y: 0.0, z: -1.0 },
+    };
+    let distance = triangle.intersect(&ray).unwrap();
+    assert!((distance - 2.0).abs() < 1e-3);
+}
+
+#[test]
+fn test_triangle_intersect_misses_outside_edges() {
+    let triangle = test_triangle();
+    let ray = Ray {
+        origin: Point { x: 5.0, y: 5.0, z: 0.0 },
+        direction: Vector3 { x: 0.0, y: 0.0, z: -1.0 },
+    };
+    assert!(triangle.intersect(&ray).is_none());
+}
+
+#[test]
+fn test_triangle_intersect_misses_behind_ray() {
+    let triangle = test_triangle();
+    let ray = Ray {
+        origin: Point { x: 0.0, y: -0.3333, z: -5.0 },
+        direction: Vector3 { x: 0.0, y: 0.0, z: -1.0 },
+    };
+    assert!(triangle.intersect(&ray).is_none());
+}
+
+#[test]
+fn test_create_transmission_bends_ray_into_denser_medium() {
+    let surface_normal = Vector3 { x: 0.0, y: 1.0, z: 0.0 };
+    let incoming = Vector3 { x: 0.0, y: -1.0, z: 0.0 };
+    let transmitted = Ray::create_transmission(surface_normal, incoming, Point { x: 0.0, y: 0.0, z: 0.0 }, 1e-10, 1.5).unwrap();
+    // A straight-on ray isn't bent by Snell's law regardless of the index change.
+    assert!((transmitted.direction.y - (-1.0)).abs() < 1e-6);
+}
+
+#[test]
+fn test_create_transmission_total_internal_reflection() {
+    let surface_normal = Vector3 { x: 0.0, y: 1.0, z: 0.0 };
+    // A steep, near-grazing ray exiting a dense medium (eta_i > eta_t) exceeds the critical
+    // angle and should report total internal reflection instead of a transmitted ray.
+    let incoming = Vector3 { x: 0.999, y: 0.045, z: 0.0 }.normalize();
+    let transmitted = Ray::create_transmission(surface_normal, incoming, Point { x: 0.0, y: 0.0, z: 0.0 }, 1e-10, 1.5);
+    assert!(transmitted.is_none());
 }
\ No newline at end of file
@@ -1,7 +1,9 @@
 use std::ops::{Add, Sub, Mul};
 use image::*;
+use serde::Deserialize;
+use super::bvh::Bvh;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Deserialize)]
 pub struct Color {
     pub red: f32,
     pub green: f32,
@@ -41,11 +43,21 @@ impl Mul<f32> for Color {
     }
 }
 
+#[derive(Clone, Deserialize)]
 pub struct Material {
     pub skin: Coloration,
     pub albedo: f32,
+    #[serde(default)]
+    pub reflectivity: f32,
+    #[serde(default)]
+    pub refractive_index: Option<f32>,
+    #[serde(default)]
+    pub transparency: Option<f32>,
+    #[serde(default)]
+    pub emissive: Option<Color>,
 }
 
+#[derive(Clone)]
 pub enum Coloration {
     Color(Color),
     Texture(DynamicImage),
@@ -86,7 +98,7 @@ impl Coloration {
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Deserialize)]
 pub struct Vector3 {
     pub x: f64, 
     pub y: f64, 
@@ -158,37 +170,81 @@ impl Vector3 {
     }
 }
 
+#[derive(Clone, Copy)]
+pub struct Aabb {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Aabb {
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: Point {
+                x: self.min.x.min(other.min.x),
+                y: self.min.y.min(other.min.y),
+                z: self.min.z.min(other.min.z),
+            },
+            max: Point {
+                x: self.max.x.max(other.max.x),
+                y: self.max.y.max(other.max.y),
+                z: self.max.z.max(other.max.z),
+            },
+        }
+    }
+
+    pub fn centroid(&self) -> Point {
+        (self.min + self.max) * 0.5
+    }
+}
+
+#[derive(Deserialize)]
 pub struct Sphere {
     pub center: Point,
     pub radius: f64,
     pub material: Material,
 }
 
+#[derive(Deserialize)]
 pub struct Plane {
     pub p0: Point,
     pub normal: Vector3,
     pub material: Material,
 }
 
+#[derive(Deserialize)]
+pub struct Triangle {
+    pub a: Point,
+    pub b: Point,
+    pub c: Point,
+    #[serde(default)]
+    pub normals: Option<(Vector3, Vector3, Vector3)>,
+    pub material: Material,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
 pub enum Element {
     Sphere(Sphere),
     Plane(Plane),
+    Triangle(Triangle),
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Deserialize)]
 pub struct DirectionalLight {
     pub direction: Vector3,
     pub color: Color,
     pub intensity: f32,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Deserialize)]
 pub struct SphericalLight {
     pub point: Point,
     pub color: Color,
     pub intensity: f32,
 }
 
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
 pub enum Light {
     Directional(DirectionalLight),
     Spherical(SphericalLight),
@@ -209,13 +265,26 @@ impl Light {
     }
 }
 
+#[derive(Deserialize)]
+pub struct Camera {
+    pub position: Point,
+    pub look_at: Point,
+    pub up: Vector3,
+    pub fov: f64,
+}
+
 pub struct Scene {
     pub width: u32,
     pub height: u32,
-    pub fov: f64,
+    pub camera: Camera,
     pub elements: Vec<Element>,
     pub lights: Vec<Light>,
     pub shadow_bias: f64,
+    pub max_depth: u32,
+    pub samples_per_pixel: u32,
+    pub exposure: f32,
+    pub gamma: f32,
+    pub bvh: Bvh,
 }
 
 pub struct Intersection<'a> {
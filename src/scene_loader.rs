@@ -0,0 +1,151 @@
+use std::fs;
+use serde::{Deserialize, Deserializer};
+use serde::de::Error;
+use super::scene::*;
+use super::bvh::Bvh;
+#[cfg(test)]
+use super::rendering::Ray;
+
+// `Coloration::Texture` holds a loaded `DynamicImage`, which isn't itself deserializable, so we
+// deserialize into this shadow representation first and load the referenced file ourselves.
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ColorationDe {
+    Color(Color),
+    Texture(String),
+}
+
+impl<'de> Deserialize<'de> for Coloration {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match ColorationDe::deserialize(deserializer)? {
+            ColorationDe::Color(color) => Ok(Coloration::Color(color)),
+            ColorationDe::Texture(path) => {
+                let image = image::open(&path).map_err(D::Error::custom)?;
+                Ok(Coloration::Texture(image))
+            }
+        }
+    }
+}
+
+fn default_shadow_bias() -> f64 {
+    1e-10
+}
+
+fn default_max_depth() -> u32 {
+    5
+}
+
+fn default_samples_per_pixel() -> u32 {
+    1
+}
+
+fn default_exposure() -> f32 {
+    1.0
+}
+
+fn default_gamma() -> f32 {
+    2.2
+}
+
+// Mirrors `Scene` field-for-field except for `bvh`, which isn't scene data — it's built from
+// `elements` once they're deserialized.
+#[derive(Deserialize)]
+struct SceneDe {
+    width: u32,
+    height: u32,
+    camera: Camera,
+    elements: Vec<Element>,
+    lights: Vec<Light>,
+    #[serde(default = "default_shadow_bias")]
+    shadow_bias: f64,
+    #[serde(default = "default_max_depth")]
+    max_depth: u32,
+    #[serde(default = "default_samples_per_pixel")]
+    samples_per_pixel: u32,
+    #[serde(default = "default_exposure")]
+    exposure: f32,
+    #[serde(default = "default_gamma")]
+    gamma: f32,
+}
+
+impl<'de> Deserialize<'de> for Scene {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = SceneDe::deserialize(deserializer)?;
+        let bvh = Bvh::build(&raw.elements);
+        Ok(Scene {
+            width: raw.width,
+            height: raw.height,
+            camera: raw.camera,
+            elements: raw.elements,
+            lights: raw.lights,
+            shadow_bias: raw.shadow_bias,
+            max_depth: raw.max_depth,
+            samples_per_pixel: raw.samples_per_pixel,
+            exposure: raw.exposure,
+            gamma: raw.gamma,
+            bvh,
+        })
+    }
+}
+
+impl Scene {
+    /// Loads a scene from a JSON file, resolving any `Coloration::Texture` paths relative to
+    /// the process's current directory and building the BVH over the parsed elements.
+    pub fn from_json_file(path: &str) -> Scene {
+        let contents = fs::read_to_string(path).unwrap();
+        serde_json::from_str(&contents).unwrap()
+    }
+}
+
+#[test]
+fn test_scene_json_round_trip_builds_matching_bvh() {
+    let json = r#"{
+        "width": 800,
+        "height": 600,
+        "camera": {
+            "position": {"x": 0.0, "y": 0.0, "z": 0.0},
+            "look_at": {"x": 0.0, "y": 0.0, "z": -1.0},
+            "up": {"x": 0.0, "y": 1.0, "z": 0.0},
+            "fov": 90.0
+        },
+        "elements": [
+            {
+                "type": "sphere",
+                "center": {"x": 0.0, "y": 0.0, "z": -5.0},
+                "radius": 1.0,
+                "material": {
+                    "skin": {"color": {"red": 1.0, "green": 0.0, "blue": 0.0}},
+                    "albedo": 0.5
+                }
+            }
+        ],
+        "lights": [
+            {
+                "type": "directional",
+                "direction": {"x": 0.0, "y": -1.0, "z": 0.0},
+                "color": {"red": 1.0, "green": 1.0, "blue": 1.0},
+                "intensity": 1.0
+            }
+        ]
+    }"#;
+
+    let scene: Scene = serde_json::from_str(json).unwrap();
+    assert_eq!(scene.width, 800);
+    assert_eq!(scene.elements.len(), 1);
+    // Defaults not present in the JSON should fall back to the `default_*` functions.
+    assert_eq!(scene.max_depth, 5);
+    assert_eq!(scene.samples_per_pixel, 1);
+    assert_eq!(scene.gamma, 2.2);
+
+    let ray = Ray {
+        origin: Point { x: 0.0, y: 0.0, z: 0.0 },
+        direction: Vector3 { x: 0.0, y: 0.0, z: -1.0 },
+    };
+    assert!(scene.bvh.trace(&scene.elements, &ray).is_some());
+}